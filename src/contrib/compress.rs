@@ -0,0 +1,235 @@
+use core::{Request, Response};
+use futures::{Future, Stream, Poll, Async};
+use hyper::header::{AcceptEncoding, ContentEncoding, ContentLength, Encoding, Vary};
+use hyper::Chunk;
+use proto::{FutureResponse, Middleware, Next};
+use std::io::Write;
+
+use flate2::write::{DeflateEncoder, GzEncoder};
+use flate2::Compression;
+
+/// Content-types that are already compressed (or otherwise not worth
+/// re-compressing) and are skipped by default.
+const DEFAULT_SKIPPED_TYPES: &[&str] = &[
+	"image/", "video/", "audio/", "application/zip", "application/gzip",
+	"application/octet-stream", "font/",
+];
+
+/// Responses smaller than this are left alone — the framing overhead of
+/// gzip/deflate/br usually outweighs the savings.
+const DEFAULT_MIN_SIZE: u64 = 860;
+
+/// Picks the best codec supported by both arc-reactor and the client, in
+/// `br > gzip > deflate` priority order, and transparently compresses the
+/// `Response` body to match.
+///
+/// Negotiation needs the request's `Accept-Encoding` header and mutates the
+/// eventual response, so `Compress` wraps the handler with `Middleware`
+/// rather than splitting across `MiddleWare<Request>`/`MiddleWare<Response>`:
+///
+/// ```rust,ignore
+/// ArcHandler::new(before, after, IndexRoute).wrap(Compress::new())
+/// ```
+#[derive(Clone)]
+pub struct Compress {
+	min_size: u64,
+	skip_content_types: Vec<String>,
+}
+
+impl Compress {
+	pub fn new() -> Self {
+		Compress {
+			min_size: DEFAULT_MIN_SIZE,
+			skip_content_types: DEFAULT_SKIPPED_TYPES.iter().map(|s| s.to_string()).collect(),
+		}
+	}
+
+	/// Responses smaller than `bytes` are never compressed. Defaults to 860.
+	///
+	/// This only applies to responses that already carry a `Content-Length`
+	/// header (e.g. `StaticFiles`). A response without one is compressed
+	/// regardless of `min_size` — measuring it would mean buffering the
+	/// whole body up front, which defeats feeding the encoder chunk-by-chunk
+	/// as it streams in.
+	pub fn min_size(mut self, bytes: u64) -> Self {
+		self.min_size = bytes;
+		self
+	}
+
+	/// Adds a content-type prefix (e.g. `"image/"`) that should never be
+	/// compressed, on top of the built-in skip list.
+	pub fn skip_content_type(mut self, prefix: &str) -> Self {
+		self.skip_content_types.push(prefix.to_string());
+		self
+	}
+
+	fn should_compress(&self, res: &Response) -> bool {
+		if let Some(len) = res.headers().get::<ContentLength>() {
+			if len.0 < self.min_size {
+				return false;
+			}
+		}
+
+		let content_type = res.headers()
+			.get_raw("Content-Type")
+			.and_then(|raw| raw.one())
+			.and_then(|raw| ::std::str::from_utf8(raw).ok())
+			.unwrap_or("");
+
+		!self.skip_content_types.iter().any(|skipped| content_type.starts_with(skipped.as_str()))
+	}
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Codec {
+	Brotli,
+	Gzip,
+	Deflate,
+}
+
+impl Codec {
+	fn negotiate(req: &Request) -> Option<Codec> {
+		let accepted = req.headers().get::<AcceptEncoding>()?;
+		let mut best: Option<(Codec, f32)> = None;
+		for qitem in accepted.iter() {
+			let codec = match qitem.item {
+				Encoding::Brotli => Codec::Brotli,
+				Encoding::Gzip => Codec::Gzip,
+				Encoding::Deflate => Codec::Deflate,
+				_ => continue,
+			};
+			let quality: f32 = qitem.quality.into();
+			if quality <= 0. {
+				continue;
+			}
+			let priority = codec.priority();
+			match best {
+				Some((_, best_priority)) if best_priority >= priority => {}
+				_ => best = Some((codec, priority)),
+			}
+		}
+		best.map(|(codec, _)| codec)
+	}
+
+	// higher wins: br > gzip > deflate
+	fn priority(&self) -> f32 {
+		match *self {
+			Codec::Brotli => 3.,
+			Codec::Gzip => 2.,
+			Codec::Deflate => 1.,
+		}
+	}
+
+	fn content_coding(&self) -> Encoding {
+		match *self {
+			Codec::Brotli => Encoding::Brotli,
+			Codec::Gzip => Encoding::Gzip,
+			Codec::Deflate => Encoding::Deflate,
+		}
+	}
+}
+
+impl Middleware for Compress {
+	fn handle(&self, req: Request, next: Next) -> FutureResponse {
+		let codec = Codec::negotiate(&req);
+		let this = self.clone();
+
+		box next.run(req).map(move |mut res| {
+			let codec = match codec {
+				Some(codec) if this.should_compress(&res) => codec,
+				_ => return res,
+			};
+
+			res.headers_mut().set(ContentEncoding(vec![codec.content_coding()]));
+			res.headers_mut().remove::<ContentLength>();
+			res.headers_mut().set(Vary::Items(vec!["Accept-Encoding".parse().unwrap()]));
+
+			let body = res.take_body();
+			res.set_body(CompressedStream::new(codec, body));
+			res
+		})
+	}
+}
+
+/// Feeds each incoming `hyper::Chunk` through the chosen encoder as it
+/// arrives, instead of buffering the whole streaming body (e.g. a
+/// `FileStream`) in memory before compressing it.
+struct CompressedStream<S> {
+	inner: S,
+	encoder: Encoder,
+	done: bool,
+}
+
+enum Encoder {
+	Gzip(GzEncoder<Vec<u8>>),
+	Deflate(DeflateEncoder<Vec<u8>>),
+	Brotli(::brotli::CompressorWriter<Vec<u8>>),
+}
+
+impl Encoder {
+	fn new(codec: Codec) -> Self {
+		match codec {
+			Codec::Gzip => Encoder::Gzip(GzEncoder::new(Vec::new(), Compression::fast())),
+			Codec::Deflate => Encoder::Deflate(DeflateEncoder::new(Vec::new(), Compression::fast())),
+			Codec::Brotli => Encoder::Brotli(::brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22)),
+		}
+	}
+
+	fn encode(&mut self, chunk: &[u8]) -> Chunk {
+		match *self {
+			Encoder::Gzip(ref mut enc) => {
+				enc.write_all(chunk).expect("in-memory gzip write can't fail");
+				Chunk::from(enc.get_mut().split_off(0))
+			}
+			Encoder::Deflate(ref mut enc) => {
+				enc.write_all(chunk).expect("in-memory deflate write can't fail");
+				Chunk::from(enc.get_mut().split_off(0))
+			}
+			Encoder::Brotli(ref mut enc) => {
+				enc.write_all(chunk).expect("in-memory brotli write can't fail");
+				enc.flush().expect("in-memory brotli flush can't fail");
+				Chunk::from(enc.get_mut().split_off(0))
+			}
+		}
+	}
+
+	fn finish(self) -> Chunk {
+		match self {
+			Encoder::Gzip(enc) => Chunk::from(enc.finish().expect("in-memory gzip finish can't fail")),
+			Encoder::Deflate(enc) => Chunk::from(enc.finish().expect("in-memory deflate finish can't fail")),
+			Encoder::Brotli(mut enc) => {
+				enc.flush().expect("in-memory brotli flush can't fail");
+				Chunk::from(enc.into_inner())
+			}
+		}
+	}
+}
+
+impl<S> CompressedStream<S> {
+	fn new(codec: Codec, inner: S) -> Self {
+		CompressedStream { inner, encoder: Encoder::new(codec), done: false }
+	}
+}
+
+impl<S> Stream for CompressedStream<S>
+	where S: Stream<Item = Chunk>
+	{
+	type Item = Chunk;
+	type Error = S::Error;
+
+	fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+		if self.done {
+			return Ok(Async::Ready(None));
+		}
+
+		match try_ready!(self.inner.poll()) {
+			Some(chunk) => Ok(Async::Ready(Some(self.encoder.encode(&chunk)))),
+			None => {
+				self.done = true;
+				let placeholder = Encoder::new(Codec::Deflate);
+				let encoder = ::std::mem::replace(&mut self.encoder, placeholder);
+				Ok(Async::Ready(Some(encoder.finish())))
+			}
+		}
+	}
+}
@@ -0,0 +1,166 @@
+use core::{Request, Response};
+use futures::future::{Future, IntoFuture};
+use hyper::header::{
+	AccessControlAllowCredentials, AccessControlAllowHeaders, AccessControlAllowMethods,
+	AccessControlAllowOrigin, AccessControlMaxAge, AccessControlRequestMethod, Headers, Vary,
+};
+use hyper::{Method, StatusCode};
+use proto::MiddleWare;
+
+/// Which request `Origin`s a [`Cors`](struct.Cors.html) policy allows.
+#[derive(Clone)]
+pub enum Origin {
+	/// Allows every origin, answering with `Access-Control-Allow-Origin: *`.
+	///
+	/// If credentials are also allowed, `*` is invalid per the Fetch spec, so
+	/// `Cors` downgrades this to echoing back the request's `Origin` instead.
+	Any,
+	/// Allows exactly one origin.
+	Exact(String),
+	/// Allows any origin in the list, echoing it back with `Vary: Origin`.
+	List(Vec<String>),
+}
+
+impl Origin {
+	fn allow(&self, origin: &str, credentials: bool) -> Option<AccessControlAllowOrigin> {
+		match *self {
+			Origin::Any if credentials => Some(AccessControlAllowOrigin::Value(origin.to_string())),
+			Origin::Any => Some(AccessControlAllowOrigin::Any),
+			Origin::Exact(ref allowed) if allowed == origin => {
+				Some(AccessControlAllowOrigin::Value(allowed.clone()))
+			}
+			Origin::List(ref allowed) if allowed.iter().any(|o| o == origin) => {
+				Some(AccessControlAllowOrigin::Value(origin.to_string()))
+			}
+			_ => None,
+		}
+	}
+}
+
+/// A tide-style CORS middleware: on an `OPTIONS` preflight request carrying
+/// `Access-Control-Request-Method`, short-circuits with a `204 No Content`
+/// carrying the allow-* headers; on every other request, lets it through and
+/// tags the outgoing `Response` with the allow-origin headers.
+///
+/// Implemented against the legacy `MiddleWare<Request>`/`MiddleWare<Response>`
+/// split, like `RateLimit` and `Filter`, so it composes with
+/// `mw!`/`arc!` instead of requiring a separate `ArcHandler::wrap` call. The
+/// `before` half resolves the allowed origin and stashes it with
+/// [`Request::share`](../core/struct.Request.html#method.share); the `after`
+/// half picks it up with
+/// [`Response::take_shared`](../core/struct.Response.html#method.take_shared)
+/// to tag the response that `before` never gets to see.
+///
+/// ```rust,ignore
+/// let cors = Cors::new()
+/// 	.allow_origin(Origin::Any)
+/// 	.allow_methods(&[Method::Get, Method::Post]);
+///
+/// router.get("/", arc!(mw![cors.clone()], mw![cors], IndexRoute));
+/// ```
+#[derive(Clone)]
+pub struct Cors {
+	origin: Origin,
+	methods: Vec<Method>,
+	headers: Vec<String>,
+	credentials: bool,
+	max_age: Option<u32>,
+}
+
+impl Cors {
+	pub fn new() -> Self {
+		Cors {
+			origin: Origin::Any,
+			methods: vec![Method::Get, Method::Post, Method::Put, Method::Delete, Method::Options],
+			headers: Vec::new(),
+			credentials: false,
+			max_age: None,
+		}
+	}
+
+	pub fn allow_origin(mut self, origin: Origin) -> Self {
+		self.origin = origin;
+		self
+	}
+
+	pub fn allow_methods(mut self, methods: &[Method]) -> Self {
+		self.methods = methods.to_vec();
+		self
+	}
+
+	pub fn allow_headers(mut self, headers: &[&str]) -> Self {
+		self.headers = headers.iter().map(|h| h.to_string()).collect();
+		self
+	}
+
+	pub fn allow_credentials(mut self, allow: bool) -> Self {
+		self.credentials = allow;
+		self
+	}
+
+	pub fn max_age(mut self, seconds: u32) -> Self {
+		self.max_age = Some(seconds);
+		self
+	}
+
+	fn tag(&self, headers: &mut Headers, origin: &str) {
+		let allow_origin = match self.origin.allow(origin, self.credentials) {
+			Some(allow_origin) => allow_origin,
+			None => return,
+		};
+
+		headers.set(allow_origin);
+		headers.set(Vary::Items(vec!["Origin".parse().unwrap()]));
+		if self.credentials {
+			headers.set(AccessControlAllowCredentials);
+		}
+	}
+}
+
+fn origin_header(req: &Request) -> Option<String> {
+	req.headers()
+		.get_raw("Origin")
+		.and_then(|raw| raw.one())
+		.and_then(|raw| ::std::str::from_utf8(raw).ok())
+		.map(str::to_string)
+}
+
+impl MiddleWare<Request> for Cors {
+	fn call(&self, req: Request) -> Box<Future<Item = Request, Error = Response>> {
+		let origin = origin_header(&req);
+		let is_preflight = *req.method() == Method::Options && req.headers().has::<AccessControlRequestMethod>();
+
+		if is_preflight {
+			let mut res = Response::new();
+			if let Some(ref origin) = origin {
+				self.tag(res.headers_mut(), origin);
+			}
+			res.headers_mut().set(AccessControlAllowMethods(self.methods.clone()));
+			if !self.headers.is_empty() {
+				res.headers_mut().set(AccessControlAllowHeaders(
+					self.headers.iter().filter_map(|h| h.parse().ok()).collect(),
+				));
+			}
+			if let Some(max_age) = self.max_age {
+				res.headers_mut().set(AccessControlMaxAge(max_age));
+			}
+			res.set_status(StatusCode::NoContent);
+			return box Err(res).into_future();
+		}
+
+		if let Some(origin) = origin {
+			req.share(origin);
+		}
+
+		box Ok(req).into_future()
+	}
+}
+
+impl MiddleWare<Response> for Cors {
+	fn call(&self, mut res: Response) -> Box<Future<Item = Response, Error = Response>> {
+		if let Some(origin) = res.take_shared::<String>() {
+			self.tag(res.headers_mut(), &origin);
+		}
+		box Ok(res).into_future()
+	}
+}
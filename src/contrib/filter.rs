@@ -0,0 +1,91 @@
+use core::{Request, Response};
+use futures::future::{Future, IntoFuture};
+use hyper::StatusCode;
+use proto::MiddleWare;
+use std::sync::Arc;
+
+/// A `before` middleware that only lets a `Request` through when an async
+/// predicate resolves `true`, generalizing the ad-hoc auth-check pattern
+/// shown in the [`MiddleWare`](trait.MiddleWare.html) docs into a reusable
+/// combinator.
+///
+/// ```rust,ignore
+/// let hasAccessToken = Filter::new(|req: &Request| {
+/// 	db::fetchUserFromToken(req.query::<AccessToken>())
+/// 		.map(|user| user.is_some())
+/// }).reject_with(|| {
+/// 	let mut res = Response::new();
+/// 	res.set_status(StatusCode::Unauthorized);
+/// 	res
+/// });
+///
+/// router.get("/user", arc!(mw![hasAccessToken], UserService));
+/// ```
+///
+/// Because the predicate is itself a future (e.g. a DB lookup), it can only
+/// ever borrow the `Request` — `req` has to stay alive until the predicate
+/// resolves before `Filter` can hand ownership of it to the inner handler.
+/// Rather than cloning the whole `Request`, `Filter` moves it behind an
+/// `Arc` for the duration of the predicate and reclaims it once the only
+/// reference left is its own.
+pub struct Filter<P> {
+	predicate: Arc<P>,
+	reject: Arc<Fn() -> Response + Send + Sync>,
+}
+
+impl<P> Clone for Filter<P> {
+	fn clone(&self) -> Self {
+		Filter {
+			predicate: self.predicate.clone(),
+			reject: self.reject.clone(),
+		}
+	}
+}
+
+impl<P, F> Filter<P>
+	where P: Fn(&Request) -> F + Send + Sync + 'static,
+	      F: IntoFuture<Item = bool, Error = Response> + 'static
+	{
+	/// Defaults the rejection to a bare `403 Forbidden`; override it with
+	/// [`reject_with`](#method.reject_with).
+	pub fn new(predicate: P) -> Self {
+		Filter {
+			predicate: Arc::new(predicate),
+			reject: Arc::new(|| {
+				let mut res = Response::new();
+				res.set_status(StatusCode::Forbidden);
+				res
+			}),
+		}
+	}
+
+	/// Sets the `Response` returned when the predicate resolves `false`.
+	/// Takes a builder rather than a `Response` directly, since a `Response`
+	/// is built once per rejection and isn't itself `Clone`.
+	pub fn reject_with<R>(mut self, rejection: R) -> Self
+		where R: Fn() -> Response + Send + Sync + 'static
+		{
+		self.reject = Arc::new(rejection);
+		self
+	}
+}
+
+impl<P, F> MiddleWare<Request> for Filter<P>
+	where P: Fn(&Request) -> F + Send + Sync + 'static,
+	      F: IntoFuture<Item = bool, Error = Response> + 'static
+	{
+	fn call(&self, req: Request) -> Box<Future<Item = Request, Error = Response>> {
+		let req = Arc::new(req);
+		let borrowed = req.clone();
+		let reject = self.reject.clone();
+
+		box (self.predicate)(&borrowed).into_future().then(move |result| {
+			drop(borrowed);
+			match result {
+				Ok(true) => Ok(Arc::try_unwrap(req).ok().expect("Filter predicate outlived the request it was given")),
+				Ok(false) => Err((reject)()),
+				Err(res) => Err(res),
+			}
+		})
+	}
+}
@@ -0,0 +1,15 @@
+//! Batteries-included middleware and services that ship with arc-reactor but
+//! aren't part of the core framework. Nothing in here is required to use
+//! arc-reactor — pull in only what you need.
+
+mod compress;
+mod cors;
+mod filter;
+mod rate_limit;
+mod static_files;
+
+pub use self::compress::Compress;
+pub use self::cors::{Cors, Origin};
+pub use self::filter::Filter;
+pub use self::rate_limit::RateLimit;
+pub use self::static_files::StaticFiles;
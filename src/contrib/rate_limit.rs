@@ -0,0 +1,153 @@
+use core::{Request, Response};
+use futures::future::{Future, IntoFuture};
+use hyper::header::RetryAfter;
+use hyper::StatusCode;
+use proto::MiddleWare;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Number of independently-locked shards the bucket map is split across, to
+/// keep one hot key from serializing every other key behind the same lock.
+const SHARDS: usize = 16;
+
+/// How many accesses a shard takes between lazy-eviction sweeps, so the
+/// O(n) `retain` scan isn't paid on every single request.
+const EVICT_EVERY: usize = 256;
+
+/// A token bucket, refilled at `rate` tokens per `period` up to `capacity`.
+struct Bucket {
+	tokens: f64,
+	last_refill: Instant,
+}
+
+impl Bucket {
+	fn new(capacity: f64) -> Self {
+		Bucket { tokens: capacity, last_refill: Instant::now() }
+	}
+
+	/// Refills based on elapsed time, then tries to take one token.
+	/// Returns `Ok(())` if a token was available, or `Err(wait)` — the
+	/// duration until the next token will be ready — otherwise.
+	fn take(&mut self, capacity: f64, rate: f64, period: Duration) -> Result<(), Duration> {
+		let now = Instant::now();
+		let elapsed = now.duration_since(self.last_refill);
+		self.last_refill = now;
+
+		let refill = elapsed.as_secs() as f64 + f64::from(elapsed.subsec_nanos()) / 1_000_000_000.;
+		self.tokens = (self.tokens + refill * rate / period_secs(period)).min(capacity);
+
+		if self.tokens >= 1. {
+			self.tokens -= 1.;
+			Ok(())
+		} else {
+			let missing = 1. - self.tokens;
+			let wait_secs = missing * period_secs(period) / rate;
+			Err(Duration::new(wait_secs as u64, ((wait_secs.fract()) * 1_000_000_000.) as u32))
+		}
+	}
+}
+
+fn period_secs(period: Duration) -> f64 {
+	period.as_secs() as f64 + f64::from(period.subsec_nanos()) / 1_000_000_000.
+}
+
+/// One lock's worth of the bucket map. Keys hash into a shard, so
+/// concurrent requests for different keys rarely contend on the same
+/// `Mutex`.
+struct Shard {
+	buckets: Mutex<HashMap<String, Bucket>>,
+	accesses: AtomicUsize,
+}
+
+impl Shard {
+	fn new() -> Self {
+		Shard { buckets: Mutex::new(HashMap::new()), accesses: AtomicUsize::new(0) }
+	}
+}
+
+fn shard_for(shards: &[Shard], key: &str) -> &Shard {
+	let mut hasher = DefaultHasher::new();
+	key.hash(&mut hasher);
+	&shards[hasher.finish() as usize % shards.len()]
+}
+
+/// A per-key token-bucket rate limiter, for use in the `before` slot.
+///
+/// ```rust,ignore
+/// let app_middlewares = mw![RateLimit::per_ip(20, 20, Duration::from_secs(60))];
+/// ```
+#[derive(Clone)]
+pub struct RateLimit {
+	capacity: f64,
+	rate: f64,
+	period: Duration,
+	key: Arc<Fn(&Request) -> String + Send + Sync>,
+	shards: Arc<Vec<Shard>>,
+}
+
+impl RateLimit {
+	/// `capacity` tokens, refilled at `rate` tokens per `period`, keyed by a
+	/// caller-supplied function of the `Request`.
+	pub fn new<K>(capacity: u64, rate: u64, period: Duration, key: K) -> Self
+		where K: Fn(&Request) -> String + Send + Sync + 'static
+		{
+		RateLimit {
+			capacity: capacity as f64,
+			rate: rate as f64,
+			period,
+			key: Arc::new(key),
+			shards: Arc::new((0..SHARDS).map(|_| Shard::new()).collect()),
+		}
+	}
+
+	/// Keys the bucket by the client's remote IP, as injected into
+	/// `req.remote` by `RootService`.
+	pub fn per_ip(capacity: u64, rate: u64, period: Duration) -> Self {
+		Self::new(capacity, rate, period, |req: &Request| {
+			req.remote()
+				.map(|addr| addr.ip())
+				.unwrap_or(IpAddr::from([0, 0, 0, 0]))
+				.to_string()
+		})
+	}
+}
+
+impl MiddleWare<Request> for RateLimit {
+	fn call(&self, req: Request) -> Box<Future<Item = Request, Error = Response>> {
+		let key = (self.key)(&req);
+		let shard = shard_for(&self.shards, &key);
+
+		let mut buckets = shard.buckets.lock().expect("rate-limit bucket map poisoned");
+
+		// Lazily evict buckets that have been full (i.e. untouched) for a
+		// while, instead of running a background sweep — there's no
+		// dedicated executor to own one. Only pay the O(n) scan once every
+		// `EVICT_EVERY` accesses to this shard, not on every request.
+		if shard.accesses.fetch_add(1, Ordering::Relaxed) % EVICT_EVERY == 0 {
+			let stale_after = self.period * 10;
+			let (capacity, rate, period) = (self.capacity, self.rate, self.period);
+			buckets.retain(|_, bucket| {
+				let idle = bucket.last_refill.elapsed();
+				let refilled = bucket.tokens + period_secs(idle) * rate / period_secs(period);
+				idle < stale_after || refilled < capacity
+			});
+		}
+
+		let bucket = buckets.entry(key).or_insert_with(|| Bucket::new(self.capacity));
+
+		match bucket.take(self.capacity, self.rate, self.period) {
+			Ok(()) => box Ok(req).into_future(),
+			Err(wait) => {
+				let mut res = Response::new();
+				res.set_status(StatusCode::TooManyRequests);
+				res.headers_mut().set(RetryAfter::Delay(wait));
+				box Err(res).into_future()
+			}
+		}
+	}
+}
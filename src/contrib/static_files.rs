@@ -0,0 +1,180 @@
+use core::{FileMeta, FileStream, Request, Response};
+use futures::{future, Future};
+use hyper::header::{
+	AcceptRanges, ByteRangeSpec, ContentLength, ContentRange, ContentRangeSpec, ContentType,
+	EntityTag, ETag, IfModifiedSince, IfNoneMatch, LastModified, Range, RangeUnit,
+};
+use hyper::StatusCode;
+use proto::{ArcService, FutureResponse};
+use std::io::SeekFrom;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio_core::reactor::Handle;
+use tokio::fs::File;
+
+/// Serves the contents of a directory over HTTP.
+///
+/// ```rust,ignore
+/// router.get("/assets/*path", StaticFiles::mount("/assets", "./public"));
+/// ```
+///
+/// Supports `Range` requests (single range, `206 Partial Content`), and
+/// conditional `GET`s via `Last-Modified`/`ETag` (`304 Not Modified`).
+pub struct StaticFiles {
+	mount_path: String,
+	root: PathBuf,
+}
+
+impl StaticFiles {
+	pub fn mount(mount_path: &str, root: &str) -> Self {
+		StaticFiles {
+			mount_path: mount_path.trim_right_matches('/').to_string(),
+			root: PathBuf::from(root),
+		}
+	}
+
+	/// Resolves the request path against `root`, rejecting any path that
+	/// escapes it (`..`, absolute components, etc).
+	fn resolve(&self, req: &Request) -> Option<PathBuf> {
+		let relative = req.path().trim_left_matches(&self.mount_path as &str).trim_left_matches('/');
+
+		let mut resolved = self.root.clone();
+		for component in Path::new(relative).components() {
+			match component {
+				Component::Normal(part) => resolved.push(part),
+				Component::CurDir => {}
+				_ => return None, // `..`, root, prefix components: traversal attempt.
+			}
+		}
+		Some(resolved)
+	}
+}
+
+impl ArcService for StaticFiles {
+	fn call(&self, req: Request, mut res: Response) -> FutureResponse {
+		let path = match self.resolve(&req) {
+			Some(path) => path,
+			None => {
+				res.set_status(StatusCode::BadRequest);
+				return box future::ok(res);
+			}
+		};
+
+		let handle: Handle = req.handle.clone().expect("FileStream needs a reactor handle");
+		let range = req.headers().get::<Range>().cloned();
+		let if_none_match = req.headers().get::<IfNoneMatch>().cloned();
+		let if_modified_since = req.headers().get::<IfModifiedSince>().cloned();
+
+		box File::open(path, &handle)
+			.and_then(|file| FileMeta(file))
+			.then(move |result| -> Box<Future<Item = Response, Error = Response>> {
+				let (file, metadata) = match result {
+					Ok(pair) => pair,
+					Err(_) => {
+						res.set_status(StatusCode::NotFound);
+						return box future::ok(res);
+					}
+				};
+
+				let len = metadata.len();
+				let modified = metadata
+					.modified()
+					.ok()
+					.and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+					.map(|duration| duration.as_secs());
+				let etag = EntityTag::weak(format!("{}-{}", len, modified.unwrap_or(0)));
+
+				let not_modified = if_none_match.map(|inm| if_none_match_matches(&inm, &etag)).unwrap_or(false)
+					|| if_modified_since
+						.and_then(|ims| modified.map(|secs| (ims, secs)))
+						.map(|(ims, secs)| {
+							let resource_modified = UNIX_EPOCH + Duration::from_secs(secs);
+							SystemTime::from(ims.0) >= resource_modified
+						})
+						.unwrap_or(false);
+
+				res.headers_mut().set(ETag(etag));
+				if let Some(secs) = modified {
+					res.headers_mut().set(LastModified(::hyper::header::HttpDate::from(UNIX_EPOCH + ::std::time::Duration::from_secs(secs))));
+				}
+				res.headers_mut().set(AcceptRanges(vec![RangeUnit::Bytes]));
+
+				if not_modified {
+					res.set_status(StatusCode::NotModified);
+					return box future::ok(res);
+				}
+
+				res.headers_mut().set(ContentType(guess_mime_type(&req)));
+
+				match range.and_then(|r| single_byte_range(&r, len)) {
+					Some((start, end)) if start <= end && end < len => {
+						let chunk_len = end - start + 1;
+						res.set_status(StatusCode::PartialContent);
+						res.headers_mut().set(ContentRange(ContentRangeSpec::Bytes {
+							range: Some((start, end)),
+							instance_length: Some(len),
+						}));
+						res.headers_mut().set(ContentLength(chunk_len));
+
+						// `FileStream::take` only caps how many bytes are read — the
+						// handle itself still needs to be seeked to `start` first.
+						box file.seek(SeekFrom::Start(start)).then(move |result| {
+							match result {
+								Ok((file, _)) => res.set_body(FileStream::new(file).take(chunk_len)),
+								Err(_) => res.set_status(StatusCode::InternalServerError),
+							}
+							Ok(res)
+						})
+					}
+					Some(_) => {
+						res.set_status(StatusCode::RangeNotSatisfiable);
+						res.headers_mut().set(ContentRange(ContentRangeSpec::Bytes { range: None, instance_length: Some(len) }));
+						box future::ok(res)
+					}
+					None => {
+						res.headers_mut().set(ContentLength(len));
+						res.set_body(FileStream::new(file));
+						box future::ok(res)
+					}
+				}
+			})
+	}
+}
+
+/// `hyper::header::IfNoneMatch` is the macro-generated 0.11-era header type,
+/// which (unlike the `headers` crate's version) has no `precondition_passes`
+/// helper — so the match against `ETag` has to be done by hand.
+fn if_none_match_matches(inm: &IfNoneMatch, etag: &EntityTag) -> bool {
+	match *inm {
+		IfNoneMatch::Any => true,
+		IfNoneMatch::Items(ref items) => items.iter().any(|item| item.weak_eq(etag)),
+	}
+}
+
+fn single_byte_range(range: &Range, len: u64) -> Option<(u64, u64)> {
+	match *range {
+		Range::Bytes(ref specs) if specs.len() == 1 => match specs[0] {
+			ByteRangeSpec::FromTo(start, end) => Some((start, end.min(len.saturating_sub(1)))),
+			ByteRangeSpec::AllFrom(start) => Some((start, len.saturating_sub(1))),
+			ByteRangeSpec::Last(n) => Some((len.saturating_sub(n), len.saturating_sub(1))),
+		},
+		_ => None,
+	}
+}
+
+fn guess_mime_type(req: &Request) -> ::hyper::mime::Mime {
+	use hyper::mime;
+
+	let ext = Path::new(req.path()).extension().and_then(|ext| ext.to_str()).unwrap_or("");
+	match ext {
+		"html" | "htm" => mime::TEXT_HTML,
+		"css" => mime::TEXT_CSS,
+		"js" => mime::TEXT_JAVASCRIPT,
+		"json" => mime::APPLICATION_JSON,
+		"png" => mime::IMAGE_PNG,
+		"jpg" | "jpeg" => mime::IMAGE_JPEG,
+		"gif" => mime::IMAGE_GIF,
+		"svg" => mime::IMAGE_SVG,
+		_ => mime::APPLICATION_OCTET_STREAM,
+	}
+}
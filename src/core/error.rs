@@ -0,0 +1,35 @@
+use std::{error::Error, fmt};
+
+/// Returned when a handler's return value fails to serialize into a JSON
+/// `Response` body.
+#[derive(Debug)]
+pub struct JsonError(pub(crate) ::serde_json::Error);
+
+impl fmt::Display for JsonError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "failed to serialize response body: {}", self.0)
+	}
+}
+
+impl Error for JsonError {
+	fn description(&self) -> &str {
+		"failed to serialize response body"
+	}
+}
+
+/// Wraps the `serde_qs` error from failing to deserialize a query string
+/// into a requested type.
+#[derive(Debug)]
+pub struct QueryParseError(pub(crate) ::serde_qs::Error);
+
+impl fmt::Display for QueryParseError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "failed to parse query string: {}", self.0)
+	}
+}
+
+impl Error for QueryParseError {
+	fn description(&self) -> &str {
+		"failed to parse query string"
+	}
+}
@@ -6,13 +6,20 @@ use tokio::{
 };
 use std::{fs::Metadata};
 use bytes::{BytesMut, BufMut};
+
+/// Size of the fixed buffer `FileStream` reads into on each poll.
+const READ_BUF_SIZE: usize = 8 * 1024;
+
 /// wraps a tokio::fs::File as a futures::Stream
 /// will produce an error if this stream isn't polled in the context of a tokio
 /// executor
 pub struct FileStream {
 	file: File,
 	buf: BytesMut,
-	flushed: bool
+	flushed: bool,
+	/// Bytes remaining to be streamed, used to cap a `Range` response to
+	/// `end - start + 1` bytes. `None` means "stream until EOF".
+	remaining: Option<u64>,
 }
 
 impl FileStream {
@@ -20,9 +27,18 @@ impl FileStream {
 		Self {
 			file,
 			buf: BytesMut::with_capacity(0),
-			flushed: true
+			flushed: true,
+			remaining: None,
 		}
 	}
+
+	/// Caps this stream to `len` bytes, for serving a single `Range` request.
+	/// The caller is expected to have already seeked `file` to the range's
+	/// start offset.
+	pub fn take(mut self, len: u64) -> Self {
+		self.remaining = Some(len);
+		self
+	}
 }
 
 impl Stream for FileStream {
@@ -30,8 +46,25 @@ impl Stream for FileStream {
 	type Error = Error;
 
 	fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+		if self.remaining == Some(0) {
+			return Ok(Async::Ready(None));
+		}
+
+		let want = self.remaining.map_or(READ_BUF_SIZE, |n| READ_BUF_SIZE.min(n as usize));
+		if want == 0 {
+			return Ok(Async::Ready(None));
+		}
+
+		// `poll_read` fills the buffer's initialized length, not its spare
+		// capacity, so size it to `want` bytes before reading.
+		self.buf.clear();
+		self.buf.extend_from_slice(&[0u8; READ_BUF_SIZE][..want]);
 		let n_bytes = try_ready!(self.file.poll_read(&mut self.buf));
 		if n_bytes > 0 {
+			self.buf.truncate(n_bytes);
+			if let Some(ref mut remaining) = self.remaining {
+				*remaining -= n_bytes as u64;
+			}
 			Ok(Async::Ready(Some(Chunk::from(self.buf.take().freeze()))))
 		} else {
 			Ok(Async::Ready(None))
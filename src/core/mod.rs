@@ -0,0 +1,15 @@
+pub(crate) mod filestream;
+mod error;
+mod reactor;
+mod request;
+mod response;
+pub(crate) mod rootservice;
+mod state;
+
+pub use self::error::{JsonError, QueryParseError};
+pub use self::filestream::{FileMeta, FileStream};
+pub use self::reactor::ArcReactor;
+pub use self::request::Request;
+pub use self::response::Response;
+pub use self::state::AppState;
+pub(crate) use self::rootservice::RootService;
@@ -0,0 +1,46 @@
+use core::AppState;
+use proto::ArcHandler;
+use routing::Router;
+
+/// Holds the configuration of an arc-reactor server: its routes, port and
+/// application state. It has no method that starts the server.
+///
+/// ```rust,ignore
+/// ArcReactor::new()
+/// 	.routes(rootRoutes())
+/// 	.port(3000)
+/// ```
+pub struct ArcReactor {
+	router: Option<Router>,
+	port: u16,
+	state: AppState,
+}
+
+impl ArcReactor {
+	pub fn new() -> Self {
+		ArcReactor {
+			router: None,
+			port: 8080,
+			state: AppState::new(()),
+		}
+	}
+
+	pub fn routes(mut self, router: Router) -> Self {
+		self.router = Some(router);
+		self
+	}
+
+	pub fn port(mut self, port: u16) -> Self {
+		self.port = port;
+		self
+	}
+
+	/// Attaches process-wide state, shared across every `Request` and
+	/// retrievable with `req.state::<S>()`. The right place for a DB pool,
+	/// config, or template engine — things that don't change per-request,
+	/// unlike the anymap-backed `req.set`/`req.get`.
+	pub fn with_state<S: Send + Sync + 'static>(mut self, state: S) -> Self {
+		self.state = AppState::new(state);
+		self
+	}
+}
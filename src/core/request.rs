@@ -0,0 +1,76 @@
+use anymap::AnyMap;
+use core::AppState;
+use hyper;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio_core::reactor::Handle;
+
+/// An incoming HTTP request, plus whatever arc-reactor or your middleware
+/// has attached to it via [`set`](#method.set)/[`get`](#method.get).
+pub struct Request {
+	inner: hyper::Request,
+	anymap: AnyMap,
+	pub(crate) handle: Option<Handle>,
+	pub(crate) remote: Option<SocketAddr>,
+	pub(crate) state: AppState,
+	pub(crate) shared: Arc<Mutex<AnyMap>>,
+}
+
+impl Request {
+	pub fn path(&self) -> &str {
+		self.inner.path()
+	}
+
+	pub fn method(&self) -> &hyper::Method {
+		self.inner.method()
+	}
+
+	pub fn headers(&self) -> &hyper::Headers {
+		self.inner.headers()
+	}
+
+	pub fn headers_mut(&mut self) -> &mut hyper::Headers {
+		self.inner.headers_mut()
+	}
+
+	/// The client's socket address, injected by `RootService`.
+	pub fn remote(&self) -> Option<SocketAddr> {
+		self.remote
+	}
+
+	/// Stashes a per-request value, retrievable later in the chain with
+	/// `get::<T>()`. Used by middleware to pass data downstream (e.g. an
+	/// authenticated `User`).
+	pub fn set<T: 'static>(&mut self, value: T) {
+		self.anymap.insert(value);
+	}
+
+	pub fn get<T: 'static>(&self) -> Option<&T> {
+		self.anymap.get::<T>()
+	}
+
+	/// Stashes a value for the `Response` this request eventually produces to
+	/// pick up with [`Response::take_shared`](struct.Response.html#method.take_shared).
+	///
+	/// This is the one channel connecting the legacy `MiddleWare<Request>`/
+	/// `MiddleWare<Response>` split — `before` only ever sees the `Request`
+	/// and `after` only ever sees the `Response`, so middleware that needs
+	/// the same piece of request data in both (e.g. echoing the `Origin`
+	/// header back onto the outgoing response) has nowhere else to put it.
+	pub fn share<T: 'static>(&self, value: T) {
+		self.shared.lock().expect("Request::shared poisoned").insert(value);
+	}
+}
+
+impl From<hyper::Request> for Request {
+	fn from(inner: hyper::Request) -> Self {
+		Request {
+			inner,
+			anymap: AnyMap::new(),
+			handle: None,
+			remote: None,
+			state: AppState::new(()),
+			shared: Arc::new(Mutex::new(AnyMap::new())),
+		}
+	}
+}
@@ -0,0 +1,70 @@
+use anymap::AnyMap;
+use hyper::{self, Body};
+use std::sync::{Arc, Mutex};
+use tokio_core::reactor::Handle;
+
+/// The HTTP response a handler or middleware builds up before it's sent back
+/// to the client.
+pub struct Response {
+	inner: hyper::Response,
+	body: Body,
+	anymap: AnyMap,
+	pub(crate) handle: Option<Handle>,
+	pub(crate) shared: Arc<Mutex<AnyMap>>,
+}
+
+impl Response {
+	pub fn new() -> Self {
+		Response {
+			inner: hyper::Response::new(),
+			body: Body::empty(),
+			anymap: AnyMap::new(),
+			handle: None,
+			shared: Arc::new(Mutex::new(AnyMap::new())),
+		}
+	}
+
+	pub fn set_status(&mut self, status: hyper::StatusCode) {
+		self.inner.set_status(status);
+	}
+
+	pub fn headers(&self) -> &hyper::Headers {
+		self.inner.headers()
+	}
+
+	pub fn headers_mut(&mut self) -> &mut hyper::Headers {
+		self.inner.headers_mut()
+	}
+
+	pub fn set_body<B: Into<Body>>(&mut self, body: B) {
+		self.body = body.into();
+	}
+
+	/// Takes the current body, leaving an empty one in its place — used by
+	/// middleware (e.g. `Compress`) that needs to wrap the existing stream.
+	pub fn take_body(&mut self) -> Body {
+		::std::mem::replace(&mut self.body, Body::empty())
+	}
+
+	pub fn set<T: 'static>(&mut self, value: T) {
+		self.anymap.insert(value);
+	}
+
+	pub fn get<T: 'static>(&self) -> Option<&T> {
+		self.anymap.get::<T>()
+	}
+
+	/// Takes a value stashed by [`Request::share`](struct.Request.html#method.share)
+	/// on the request this response is paired with. Returns `None` if nothing
+	/// of type `T` was shared — e.g. there was no matching `before`
+	/// middleware, or the request didn't qualify.
+	pub fn take_shared<T: 'static>(&self) -> Option<T> {
+		self.shared.lock().expect("Response::shared poisoned").remove::<T>()
+	}
+}
+
+impl From<Response> for hyper::Response {
+	fn from(res: Response) -> Self {
+		res.inner.with_body(res.body)
+	}
+}
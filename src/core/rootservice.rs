@@ -1,4 +1,4 @@
-use super::{Request, Response};
+use super::{AppState, Request, Response};
 use futures::Future;
 use hyper::{self, server::Service};
 use proto::{ArcHandler, ArcService};
@@ -10,6 +10,7 @@ pub(crate) struct RootService {
 	pub(crate) remote_ip: SocketAddr,
 	pub(crate) service: ArcHandler,
 	pub(crate) handle: Handle,
+	pub(crate) state: AppState,
 }
 
 impl Service for RootService {
@@ -22,8 +23,10 @@ impl Service for RootService {
 		let mut request: Request = req.into();
 		request.handle = Some(self.handle.clone());
 		request.remote = Some(self.remote_ip);
+		request.state = self.state.clone();
 		let mut res = Response::new();
 		res.handle = Some(self.handle.clone());
+		res.shared = request.shared.clone();
 		let responseFuture = AssertUnwindSafe(self.service.call(request, res)).catch_unwind();
 
 		let responseFuture =
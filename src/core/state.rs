@@ -0,0 +1,31 @@
+use core::Request;
+use std::any::Any;
+use std::sync::Arc;
+
+/// Process-wide state threaded into every `Request`, set once via
+/// [`ArcReactor::with_state`](struct.ArcReactor.html#method.with_state) and
+/// shared — not cloned per-request — across the whole application.
+///
+/// This is distinct from the anymap-backed `req.set`/`req.get`, which
+/// middleware populate per-request; `AppState` is fixed for the lifetime of
+/// the server, making it the right place for a DB pool, config, or template
+/// engine.
+#[derive(Clone)]
+pub struct AppState(Arc<Any + Send + Sync>);
+
+impl AppState {
+	pub(crate) fn new<S: Send + Sync + 'static>(state: S) -> Self {
+		AppState(Arc::new(state))
+	}
+}
+
+impl Request {
+	/// Retrieves the application state set via `ArcReactor::with_state`.
+	///
+	/// Panics if `S` isn't the type the application was built with — mirrors
+	/// `req.get::<T>()`'s anymap semantics, where the caller is expected to
+	/// know what's there.
+	pub fn state<S: Send + Sync + 'static>(&self) -> &S {
+		self.state.0.downcast_ref::<S>().expect("ArcReactor::with_state type mismatch")
+	}
+}
@@ -81,6 +81,8 @@
 #![allow(non_snake_case)]
 
 extern crate anymap;
+extern crate brotli;
+extern crate flate2;
 pub extern crate futures_await as futures;
 pub extern crate hyper;
 extern crate impl_service;
@@ -104,7 +106,7 @@ pub(crate) mod routing;
 
 pub use contrib::*;
 pub use core::{ArcReactor, JsonError, QueryParseError};
-pub use proto::{ArcHandler, ArcService, MiddleWare, FutureResponse};
+pub use proto::{ArcHandler, ArcService, Middleware, MiddleWare, Next, FutureResponse};
 pub use routing::{RouteGroup, Router};
 
 pub mod prelude {
@@ -113,7 +115,7 @@ pub mod prelude {
 	pub use futures::prelude::{async_block, await};
 	pub use futures::{Future, Stream, IntoFuture};
 	pub use impl_service::{middleware, service};
-	pub use proto::{ArcHandler, ArcService, MiddleWare};
+	pub use proto::{ArcHandler, ArcService, Middleware, MiddleWare, Next};
 }
 
 pub use hyper::header;
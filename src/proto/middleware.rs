@@ -1,7 +1,9 @@
 #![macro_use]
 use core::{Request, Response};
 use futures::future::{Future, IntoFuture};
+use proto::{ArcService, FutureResponse};
 use routing::extend_lifetime;
+use std::sync::Arc;
 
 type MiddleWareFuture<I> = Box<Future<Item = I, Error = Response>>;
 
@@ -193,3 +195,62 @@ macro_rules! mw {
      box middleWares as Box<MiddleWare<_>>
 	}};
 }
+
+/// A chain-of-responsibility style middleware.
+///
+/// Unlike `MiddleWare<Request>`/`MiddleWare<Response>`, which only ever see
+/// one side of a call, a `Middleware` wraps the rest of the chain and
+/// decides for itself whether (and when) to call into it, by invoking
+/// `next.run(req)`. This gives it true wrap-around behaviour: time a
+/// request, set up then tear down a resource around the handler, or
+/// short-circuit with a cached `Response` without ever reaching it.
+///
+/// ```rust,ignore
+/// use arc_reactor::prelude::*;
+/// use arc_reactor::FutureResponse;
+/// use std::time::Instant;
+///
+/// struct Timer;
+///
+/// impl Middleware for Timer {
+/// 	fn handle(&self, req: Request, next: Next) -> FutureResponse {
+/// 		let start = Instant::now();
+/// 		box next.run(req).map(move |res| {
+/// 			println!("request took {:?}", start.elapsed());
+/// 			res
+/// 		})
+/// 	}
+/// }
+/// ```
+pub trait Middleware: Send + Sync {
+	fn handle(&self, req: Request, next: Next) -> FutureResponse;
+}
+
+/// The remainder of a `Middleware` chain, terminated by the route handler.
+///
+/// `Next` owns its position in the chain by index into a shared,
+/// reference-counted `Vec` rather than a borrowed slice, so it can be moved
+/// into a boxed future without resorting to the `extend_lifetime` hack used
+/// by the `MiddleWare<Request>`/`MiddleWare<Response>` chains above.
+pub struct Next {
+	chain: Arc<Vec<Arc<Middleware>>>,
+	index: usize,
+	handler: Arc<ArcService>,
+	response: Response,
+}
+
+impl Next {
+	pub(crate) fn new(chain: Arc<Vec<Arc<Middleware>>>, index: usize, handler: Arc<ArcService>, response: Response) -> Self {
+		Next { chain, index, handler, response }
+	}
+
+	/// Hands `req` to the next `Middleware` in the chain, or to the route
+	/// handler once the chain is exhausted.
+	pub fn run(self, req: Request) -> FutureResponse {
+		let Next { chain, index, handler, response } = self;
+		match chain.get(index).cloned() {
+			Some(middleware) => middleware.handle(req, Next::new(chain, index + 1, handler, response)),
+			None => handler.call(req, response),
+		}
+	}
+}
@@ -0,0 +1,91 @@
+use core::{Request, Response};
+use futures::Future;
+use std::sync::Arc;
+
+#[macro_use]
+mod middleware;
+
+pub use self::middleware::{MiddleWare, Middleware, Next};
+
+/// The future returned by an `ArcService`, a `MiddleWare`, or a `Middleware`.
+pub type FutureResponse = Box<Future<Item = Response, Error = Response>>;
+
+/// Implemented by route handlers, usually generated for you by the `#[service]`
+/// proc macro (see the crate-level docs).
+pub trait ArcService: Send + Sync {
+	fn call(&self, req: Request, res: Response) -> FutureResponse;
+}
+
+impl<F> ArcService for F
+	where F: Send + Sync + Fn(Request, Response) -> FutureResponse
+	{
+	fn call(&self, req: Request, res: Response) -> FutureResponse {
+		(self)(req, res)
+	}
+}
+
+/// Glues a route handler together with the middleware that should run
+/// around it.
+///
+/// Historically this was a rigid `before: Box<MiddleWare<Request>>` /
+/// `after: Box<MiddleWare<Response>>` split. Both are still supported as-is,
+/// and wrap-around [`Middleware`](trait.Middleware.html) (added with
+/// [`wrap`](#method.wrap)) composes with them: `ArcHandler` runs `before`,
+/// then the `Middleware` chain and the handler, then `after`. `before` and
+/// `after` are dispatched by `ArcHandler` itself around the chain, not as
+/// links in it; when `before` rejects a request, the chain and the handler
+/// are skipped and `after` gets the rejection `Response`.
+pub struct ArcHandler {
+	before: Box<MiddleWare<Request>>,
+	after: Box<MiddleWare<Response>>,
+	chain: Arc<Vec<Arc<Middleware>>>,
+	service: Arc<ArcService>,
+}
+
+impl ArcHandler {
+	pub fn new<S>(before: Box<MiddleWare<Request>>, after: Box<MiddleWare<Response>>, service: S) -> Self
+		where S: ArcService + 'static
+		{
+		ArcHandler {
+			before,
+			after,
+			chain: Arc::new(Vec::new()),
+			service: Arc::new(service),
+		}
+	}
+
+	/// Appends a `Middleware` to the wrap-around chain. Chain middlewares run
+	/// after the legacy `before` middleware has let the request through, and
+	/// before the legacy `after` middleware sees the response. Chain multiple
+	/// calls to add more than one, in the order they should run:
+	///
+	/// ```rust,ignore
+	/// ArcHandler::new(before, after, IndexRoute)
+	/// 	.wrap(Timer)
+	/// 	.wrap(Compress::new())
+	/// ```
+	pub fn wrap<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+		Arc::get_mut(&mut self.chain)
+			.expect("ArcHandler::wrap called after the handler started serving requests")
+			.push(Arc::new(middleware));
+		self
+	}
+}
+
+impl ArcService for ArcHandler {
+	fn call(&self, req: Request, res: Response) -> FutureResponse {
+		let after = self.after.clone();
+		let chain = self.chain.clone();
+		let service = self.service.clone();
+
+		box self.before.call(req).then(move |result| {
+			match result {
+				Ok(req) => {
+					let next = Next::new(chain, 0, service, res);
+					box next.run(req).and_then(move |res| after.call(res)) as FutureResponse
+				}
+				Err(res) => box after.call(res) as FutureResponse,
+			}
+		})
+	}
+}